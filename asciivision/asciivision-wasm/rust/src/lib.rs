@@ -3,8 +3,10 @@ use wasm_bindgen::prelude::*;
 mod ascii;
 mod config;
 mod processor;
+mod snapshot;
 
 use config::Config;
+use processor::{AutoExposureState, GrainState, ResampleTables};
 
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -15,6 +17,9 @@ pub fn init() {
 pub struct AsciiProcessor {
     config: Config,
     output_buffer: String,
+    resample_tables: ResampleTables,
+    ae_state: AutoExposureState,
+    grain_state: GrainState,
 }
 
 #[wasm_bindgen]
@@ -24,10 +29,15 @@ impl AsciiProcessor {
         AsciiProcessor {
             config: Config::default(),
             output_buffer: String::with_capacity(200 * 100 + 100),
+            resample_tables: ResampleTables::default(),
+            ae_state: AutoExposureState::default(),
+            grain_state: GrainState::default(),
         }
     }
 
-    /// Process RGBA frame data and return ASCII string
+    /// Process RGBA frame data and return ASCII string. Dispatches to the
+    /// colored (ANSI) path when `Config.color` is set, otherwise the
+    /// monochrome path stays byte-for-byte identical.
     #[wasm_bindgen]
     pub fn process_frame(
         &mut self,
@@ -37,8 +47,53 @@ impl AsciiProcessor {
         out_width: u32,
         out_height: u32,
     ) -> String {
-        processor::process_frame(
+        if self.config.color {
+            processor::process_frame_ansi(
+                &self.config,
+                &mut self.resample_tables,
+                &mut self.ae_state,
+                &mut self.grain_state,
+                pixels,
+                src_width,
+                src_height,
+                out_width,
+                out_height,
+                &mut self.output_buffer,
+            );
+        } else {
+            processor::process_frame(
+                &self.config,
+                &mut self.resample_tables,
+                &mut self.ae_state,
+                &mut self.grain_state,
+                pixels,
+                src_width,
+                src_height,
+                out_width,
+                out_height,
+                &mut self.output_buffer,
+            );
+        }
+        self.output_buffer.clone()
+    }
+
+    /// Process RGBA frame data and return ASCII output carrying 24-bit
+    /// ANSI (SGR) color escapes per glyph, regardless of `Config.color`
+    /// (for callers that always want the colored path explicitly).
+    #[wasm_bindgen]
+    pub fn process_frame_ansi(
+        &mut self,
+        pixels: &[u8],
+        src_width: u32,
+        src_height: u32,
+        out_width: u32,
+        out_height: u32,
+    ) -> String {
+        processor::process_frame_ansi(
             &self.config,
+            &mut self.resample_tables,
+            &mut self.ae_state,
+            &mut self.grain_state,
             pixels,
             src_width,
             src_height,
@@ -49,6 +104,99 @@ impl AsciiProcessor {
         self.output_buffer.clone()
     }
 
+    #[wasm_bindgen]
+    pub fn set_color(&mut self, value: bool) {
+        self.config.color = value;
+    }
+
+    #[wasm_bindgen]
+    pub fn toggle_color(&mut self) {
+        self.config.color = !self.config.color;
+    }
+
+    /// Rasterize the current ASCII output (whatever `process_frame` or
+    /// `process_frame_ansi` last produced) into encoded PNG bytes.
+    #[wasm_bindgen]
+    pub fn capture_png(&self) -> Vec<u8> {
+        snapshot::capture_png(&self.output_buffer).unwrap_or_default()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_vignette(&mut self, value: f32) {
+        self.config.vignette = value.clamp(0.0, 1.0);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_scanlines(&mut self, value: f32) {
+        self.config.scanlines = value.clamp(0.0, 1.0);
+    }
+
+    /// Chroma-shift magnitude in source pixels; only visible in color mode.
+    #[wasm_bindgen]
+    pub fn set_rgb_shift(&mut self, value: f32) {
+        self.config.rgb_shift = value.clamp(0.0, 16.0);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_grain(&mut self, value: bool) {
+        self.config.grain = value;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_grain_shadows(&mut self, strength: f32) {
+        self.config.grain_points[0].1 = strength;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_grain_midtones(&mut self, strength: f32) {
+        self.config.grain_points[1].1 = strength;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_grain_highlights(&mut self, strength: f32) {
+        self.config.grain_points[2].1 = strength;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_auto_exposure(&mut self, value: bool) {
+        self.config.auto_exposure = value;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_ae_target(&mut self, value: f32) {
+        self.config.ae_target = value.clamp(0.0, 255.0);
+    }
+
+    /// Current smoothed auto-exposure gain (1.0 = no adjustment).
+    #[wasm_bindgen]
+    pub fn get_ae_gain(&self) -> f32 {
+        self.ae_state.gain()
+    }
+
+    /// Select the filter used to resample the source frame down to the
+    /// output grid (0=Point, 1=Triangle, 2=CatmullRom, 3=Lanczos3).
+    #[wasm_bindgen]
+    pub fn set_resample_filter(&mut self, filter: u8) {
+        use config::ResampleFilter;
+        self.config.resample_filter = match filter {
+            1 => ResampleFilter::Triangle,
+            2 => ResampleFilter::CatmullRom,
+            3 => ResampleFilter::Lanczos3,
+            _ => ResampleFilter::Point,
+        };
+    }
+
+    #[wasm_bindgen]
+    pub fn get_resample_filter(&self) -> u8 {
+        use config::ResampleFilter;
+        match self.config.resample_filter {
+            ResampleFilter::Point => 0,
+            ResampleFilter::Triangle => 1,
+            ResampleFilter::CatmullRom => 2,
+            ResampleFilter::Lanczos3 => 3,
+        }
+    }
+
     #[wasm_bindgen]
     pub fn set_brightness(&mut self, value: f32) {
         self.config.brightness = value.clamp(-1.0, 1.0);
@@ -92,6 +240,8 @@ impl AsciiProcessor {
     #[wasm_bindgen]
     pub fn reset(&mut self) {
         self.config = Config::default();
+        self.ae_state = AutoExposureState::default();
+        self.grain_state = GrainState::default();
     }
 
     #[wasm_bindgen]
@@ -1,3 +1,29 @@
+/// Resampling filter used when downscaling the source frame to the
+/// (much smaller) output grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResampleFilter {
+    /// Point/nearest-neighbor sampling (cheapest, aliases on downscale).
+    Point,
+    /// Bilinear-style triangle filter, radius 1.
+    Triangle,
+    /// Catmull-Rom cubic, radius 2.
+    CatmullRom,
+    /// Lanczos windowed sinc, radius 3.
+    Lanczos3,
+}
+
+impl ResampleFilter {
+    /// Filter support radius in source-pixel units at a 1:1 scale.
+    pub(crate) fn radius(self) -> f32 {
+        match self {
+            ResampleFilter::Point => 0.5,
+            ResampleFilter::Triangle => 1.0,
+            ResampleFilter::CatmullRom => 2.0,
+            ResampleFilter::Lanczos3 => 3.0,
+        }
+    }
+}
+
 /// Configuration settings for ASCII processing
 pub struct Config {
     /// Brightness adjustment (-1.0 to 1.0)
@@ -8,6 +34,26 @@ pub struct Config {
     pub use_detailed_ramp: bool,
     /// Invert colors
     pub invert: bool,
+    /// Filter used to resample the source frame down to the output grid
+    pub resample_filter: ResampleFilter,
+    /// Drive gain and contrast stretch from the frame's own luminance
+    /// histogram instead of relying solely on manual brightness/contrast.
+    pub auto_exposure: bool,
+    /// Target mean luminance (0-255) that auto-exposure converges toward.
+    pub ae_target: f32,
+    /// Emit 24-bit ANSI color escapes per cell instead of plain text.
+    pub color: bool,
+    /// Radial vignette strength (0.0 = off, 1.0 = corners fully dark).
+    pub vignette: f32,
+    /// Fraction to darken alternate output rows by (0.0 = off, 1.0 = fully dark).
+    pub scanlines: f32,
+    /// Chroma-shift magnitude in source pixels (color mode only, 0.0 = off).
+    pub rgb_shift: f32,
+    /// Overlay synthetic film grain onto the luminance before ramp mapping.
+    pub grain: bool,
+    /// `(luminance, strength)` control points the grain scaling LUT is
+    /// linearly interpolated from; must be sorted by luminance.
+    pub grain_points: Vec<(f32, f32)>,
 }
 
 impl Default for Config {
@@ -17,6 +63,15 @@ impl Default for Config {
             contrast: 1.0,
             use_detailed_ramp: true,
             invert: false,
+            resample_filter: ResampleFilter::Point,
+            auto_exposure: false,
+            ae_target: 0.5 * 255.0,
+            color: false,
+            vignette: 0.0,
+            scanlines: 0.0,
+            rgb_shift: 0.0,
+            grain: false,
+            grain_points: vec![(0.0, 0.0), (128.0, 0.0), (255.0, 0.0)],
         }
     }
 }
@@ -1,11 +1,36 @@
 use crate::ascii::gray_to_ascii;
-use crate::config::Config;
+use crate::config::{Config, ResampleFilter};
 
-/// Apply brightness and contrast adjustment to a grayscale value
+/// Per-frame auto-exposure/contrast-stretch parameters derived from the
+/// source histogram, composed with the manual brightness/contrast below.
+struct AutoExposureFrame {
+    /// Smoothed exposure gain (see [`AutoExposureState`]).
+    gain: f32,
+    /// Low percentile luminance, stretched to 0.
+    lo: f32,
+    /// High percentile luminance, stretched to 255.
+    hi: f32,
+}
+
+/// Apply auto-exposure (percentile contrast stretch + gain), then the
+/// manual brightness/contrast, to a grayscale value.
 #[inline]
-fn apply_brightness_contrast(gray: u8, brightness: f32, contrast: f32) -> u8 {
+fn apply_brightness_contrast(
+    gray: u8,
+    brightness: f32,
+    contrast: f32,
+    ae: Option<&AutoExposureFrame>,
+) -> u8 {
+    let mut value = gray as f32;
+
+    if let Some(ae) = ae {
+        let range = (ae.hi - ae.lo).max(1.0);
+        value = ((value - ae.lo) / range * 255.0).clamp(0.0, 255.0);
+        value = (value * ae.gain).clamp(0.0, 255.0);
+    }
+
     // Apply contrast around midpoint (127.5)
-    let mut adjusted = (gray as f32 - 127.5) * contrast + 127.5;
+    let mut adjusted = (value - 127.5) * contrast + 127.5;
 
     // Apply brightness (-1.0 to 1.0 maps to -255 to +255)
     adjusted += brightness * 255.0;
@@ -14,6 +39,73 @@ fn apply_brightness_contrast(gray: u8, brightness: f32, contrast: f32) -> u8 {
     adjusted.clamp(0.0, 255.0) as u8
 }
 
+/// Persistent auto-exposure state: the exponentially-smoothed gain that
+/// carries over from one frame to the next so exposure doesn't flicker.
+pub struct AutoExposureState {
+    gain: f32,
+}
+
+impl AutoExposureState {
+    /// Smoothing factor for the gain's exponential moving average.
+    const ALPHA: f32 = 0.1;
+
+    /// Current smoothed gain.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Pull `gain` toward `target / mean` by one smoothing step.
+    fn update(&mut self, target: f32, mean: f32) {
+        if mean > 0.0 {
+            self.gain += (target / mean - self.gain) * Self::ALPHA;
+            self.gain = self.gain.clamp(0.1, 8.0);
+        }
+    }
+}
+
+impl Default for AutoExposureState {
+    fn default() -> Self {
+        AutoExposureState { gain: 1.0 }
+    }
+}
+
+/// Mean luminance and 1st/99th percentile luminance from a 256-bin
+/// grayscale histogram.
+fn histogram_stats(histogram: &[u32; 256], total: usize) -> (f32, f32, f32) {
+    if total == 0 {
+        return (0.0, 0.0, 255.0);
+    }
+
+    let sum: u64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(gray, &count)| gray as u64 * count as u64)
+        .sum();
+    let mean = sum as f32 / total as f32;
+
+    let lo_count = (total as f32 * 0.01).round() as u32;
+    let hi_count = (total as f32 * 0.99).round() as u32;
+
+    let mut cumulative = 0u32;
+    let mut lo = 0u8;
+    let mut hi = 255u8;
+    let mut lo_found = false;
+    let mut hi_found = false;
+    for (gray, &count) in histogram.iter().enumerate() {
+        cumulative += count;
+        if !lo_found && cumulative > lo_count {
+            lo = gray as u8;
+            lo_found = true;
+        }
+        if !hi_found && cumulative >= hi_count {
+            hi = gray as u8;
+            hi_found = true;
+        }
+    }
+
+    (mean, lo as f32, hi as f32)
+}
+
 /// Convert RGBA pixel to grayscale using luminance formula
 /// Same as original: 0.299*R + 0.587*G + 0.114*B
 #[inline]
@@ -21,9 +113,432 @@ fn rgba_to_gray(r: u8, g: u8, b: u8) -> u8 {
     ((r as f32 * 0.299) + (g as f32 * 0.587) + (b as f32 * 0.114)) as u8
 }
 
+/// Evaluate a resampling kernel at normalized distance `t` (in units of
+/// source pixels at 1:1 scale).
+///
+/// The `Point` arm is unreachable: `build_axis_taps` resolves `Point` to a
+/// single nearest-index tap before this is ever called.
+#[inline]
+fn kernel(filter: ResampleFilter, t: f32) -> f32 {
+    match filter {
+        ResampleFilter::Point => unreachable!("Point is resolved in build_axis_taps"),
+        ResampleFilter::Triangle => (1.0 - t.abs()).max(0.0),
+        ResampleFilter::CatmullRom => {
+            // a = -0.5 variant of the Catmull-Rom cubic
+            let a = -0.5;
+            let x = t.abs();
+            if x < 1.0 {
+                (a + 2.0) * x * x * x - (a + 3.0) * x * x + 1.0
+            } else if x < 2.0 {
+                a * x * x * x - 5.0 * a * x * x + 8.0 * a * x - 4.0 * a
+            } else {
+                0.0
+            }
+        }
+        ResampleFilter::Lanczos3 => {
+            #[inline]
+            fn sinc(x: f32) -> f32 {
+                if x.abs() < 1e-6 {
+                    1.0
+                } else {
+                    let px = std::f32::consts::PI * x;
+                    px.sin() / px
+                }
+            }
+            if t.abs() < 3.0 {
+                sinc(t) * sinc(t / 3.0)
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// Clamp the rounded sample `center` to a valid source index.
+#[inline]
+fn nearest_index(center: f32, src_dim: usize) -> usize {
+    (center.round() as isize).clamp(0, src_dim as isize - 1) as usize
+}
+
+/// The source-index range and weights contributing to a single output
+/// sample along one axis.
+struct AxisTaps {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+/// Build one axis of separable resampling taps.
+///
+/// `mirror` reverses the output-to-source mapping (used for the horizontal
+/// axis, which is flipped for a natural webcam feel).
+fn build_axis_taps(
+    filter: ResampleFilter,
+    src_dim: u32,
+    out_dim: u32,
+    mirror: bool,
+) -> Vec<AxisTaps> {
+    let src_dim = src_dim as usize;
+    let scale = src_dim as f32 / out_dim as f32;
+
+    if filter == ResampleFilter::Point {
+        // Single nearest-source-index tap, independent of `scale` — unlike
+        // the other filters, Point's support must not grow on downscale or
+        // it stops being nearest-neighbor and becomes a box blur.
+        return (0..out_dim)
+            .map(|o| {
+                let o = if mirror { out_dim - 1 - o } else { o };
+                let center = (o as f32 + 0.5) * scale - 0.5;
+                AxisTaps {
+                    start: nearest_index(center, src_dim),
+                    weights: vec![1.0],
+                }
+            })
+            .collect();
+    }
+
+    let radius = filter.radius();
+    let support = radius * scale.max(1.0);
+
+    (0..out_dim)
+        .map(|o| {
+            let o = if mirror { out_dim - 1 - o } else { o };
+            let center = (o as f32 + 0.5) * scale - 0.5;
+
+            let lo = ((center - support).ceil() as isize).max(0);
+            let hi = ((center + support).floor() as isize).min(src_dim as isize - 1);
+            let lo = lo.min(hi) as usize;
+            let hi = hi.max(lo as isize) as usize;
+
+            let mut weights: Vec<f32> = (lo..=hi)
+                .map(|i| kernel(filter, (i as f32 - center) / scale.max(1.0)))
+                .collect();
+
+            let sum: f32 = weights.iter().sum();
+            if sum > 0.0 {
+                for w in weights.iter_mut() {
+                    *w /= sum;
+                }
+            } else {
+                // Degenerate window (can happen at the extreme edges); fall
+                // back to the nearest source pixel with full weight.
+                weights = vec![1.0];
+                return AxisTaps {
+                    start: nearest_index(center, src_dim),
+                    weights,
+                };
+            }
+
+            AxisTaps { start: lo, weights }
+        })
+        .collect()
+}
+
+/// Precomputed index+weight tables for one (src, out, filter) combination,
+/// reused across frames as long as none of those parameters change.
+pub struct ResampleTables {
+    src_width: u32,
+    src_height: u32,
+    out_width: u32,
+    out_height: u32,
+    filter: ResampleFilter,
+    x_taps: Vec<AxisTaps>,
+    y_taps: Vec<AxisTaps>,
+}
+
+impl ResampleTables {
+    fn matches(
+        &self,
+        src_width: u32,
+        src_height: u32,
+        out_width: u32,
+        out_height: u32,
+        filter: ResampleFilter,
+    ) -> bool {
+        self.src_width == src_width
+            && self.src_height == src_height
+            && self.out_width == out_width
+            && self.out_height == out_height
+            && self.filter == filter
+    }
+
+    fn build(
+        src_width: u32,
+        src_height: u32,
+        out_width: u32,
+        out_height: u32,
+        filter: ResampleFilter,
+    ) -> Self {
+        ResampleTables {
+            src_width,
+            src_height,
+            out_width,
+            out_height,
+            filter,
+            // Horizontal axis is mirrored for a natural webcam feel.
+            x_taps: build_axis_taps(filter, src_width, out_width, true),
+            y_taps: build_axis_taps(filter, src_height, out_height, false),
+        }
+    }
+
+    /// Rebuild the tables only if the resize parameters actually changed.
+    fn ensure(
+        &mut self,
+        src_width: u32,
+        src_height: u32,
+        out_width: u32,
+        out_height: u32,
+        filter: ResampleFilter,
+    ) {
+        if !self.matches(src_width, src_height, out_width, out_height, filter) {
+            *self = Self::build(src_width, src_height, out_width, out_height, filter);
+        }
+    }
+}
+
+impl Default for ResampleTables {
+    fn default() -> Self {
+        ResampleTables::build(1, 1, 1, 1, ResampleFilter::Point)
+    }
+}
+
+/// Resample one scalar source plane through the cached two-pass (vertical
+/// then horizontal) separable filter, producing an `out_height * out_width`
+/// plane.
+fn resample_plane(cache: &ResampleTables, plane: &[f32], src_width: usize) -> Vec<f32> {
+    let out_height = cache.y_taps.len();
+    let out_width = cache.x_taps.len();
+
+    // Pass 1: resample vertically (src_height -> out_height), keeping the
+    // full source width.
+    let mut vertical = vec![0.0f32; out_height * src_width];
+    for (oy, taps) in cache.y_taps.iter().enumerate() {
+        for x in 0..src_width {
+            let mut acc = 0.0;
+            for (k, &w) in taps.weights.iter().enumerate() {
+                acc += plane[(taps.start + k) * src_width + x] * w;
+            }
+            vertical[oy * src_width + x] = acc;
+        }
+    }
+
+    // Pass 2: resample horizontally (src_width -> out_width, mirrored).
+    let mut out = vec![0.0f32; out_height * out_width];
+    for oy in 0..out_height {
+        for (ox, taps) in cache.x_taps.iter().enumerate() {
+            let mut acc = 0.0;
+            for (k, &w) in taps.weights.iter().enumerate() {
+                acc += vertical[oy * src_width + taps.start + k] * w;
+            }
+            out[oy * out_width + ox] = acc;
+        }
+    }
+
+    out
+}
+
+/// Grayscale an RGBA frame into a `src_width * src_height` luminance plane,
+/// building a 256-bin histogram (for auto-exposure) along the way.
+fn grayscale_plane(pixels: &[u8], src_width: usize, src_height: usize) -> (Vec<f32>, [u32; 256]) {
+    let bytes_per_row = src_width * 4; // RGBA = 4 bytes
+    let mut gray_plane = vec![0.0f32; src_width * src_height];
+    let mut histogram = [0u32; 256];
+    for y in 0..src_height {
+        for x in 0..src_width {
+            let offset = y * bytes_per_row + x * 4;
+            let gray = if offset + 2 < pixels.len() {
+                rgba_to_gray(pixels[offset], pixels[offset + 1], pixels[offset + 2])
+            } else {
+                0
+            };
+            histogram[gray as usize] += 1;
+            gray_plane[y * src_width + x] = gray as f32;
+        }
+    }
+    (gray_plane, histogram)
+}
+
+/// Derive this frame's auto-exposure parameters (if enabled), advancing the
+/// smoothed gain in `ae_state`.
+fn auto_exposure_frame(
+    config: &Config,
+    ae_state: &mut AutoExposureState,
+    histogram: &[u32; 256],
+    sample_count: usize,
+) -> Option<AutoExposureFrame> {
+    if !config.auto_exposure {
+        return None;
+    }
+    let (mean, lo, hi) = histogram_stats(histogram, sample_count);
+    ae_state.update(config.ae_target, mean);
+    Some(AutoExposureFrame {
+        gain: ae_state.gain(),
+        lo,
+        hi,
+    })
+}
+
+/// Apply the CRT emulation stage (radial vignette, then scanline
+/// modulation) to an `out_height * out_width` luminance-like plane, in
+/// place, on the output grid.
+fn apply_crt(
+    plane: &mut [f32],
+    out_width: usize,
+    out_height: usize,
+    vignette: f32,
+    scanlines: f32,
+) {
+    if vignette <= 0.0 && scanlines <= 0.0 {
+        return;
+    }
+
+    let cx = (out_width as f32 - 1.0) / 2.0;
+    let cy = (out_height as f32 - 1.0) / 2.0;
+    let max_dist = (cx * cx + cy * cy).sqrt().max(1.0);
+
+    for oy in 0..out_height {
+        let scanline_factor = if scanlines > 0.0 && oy % 2 == 1 {
+            (1.0 - scanlines).max(0.0)
+        } else {
+            1.0
+        };
+
+        for ox in 0..out_width {
+            let idx = oy * out_width + ox;
+
+            let mut factor = scanline_factor;
+            if vignette > 0.0 {
+                let dx = ox as f32 - cx;
+                let dy = oy as f32 - cy;
+                let dist = (dx * dx + dy * dy).sqrt();
+                factor *= (1.0 - vignette * (dist / max_dist).powi(2)).max(0.0);
+            }
+
+            plane[idx] *= factor;
+        }
+    }
+}
+
+/// Side length of the square grain template (in grain cells).
+const GRAIN_TEMPLATE_SIZE: usize = 64;
+
+/// Advance a small seeded LCG (PCG-style constants) and return its state.
+#[inline]
+fn next_lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state
+}
+
+/// Build the `GRAIN_TEMPLATE_SIZE`^2 pseudo-random grain template: raw LCG
+/// noise passed through a one-pole AR(1) recurrence to decorrelate it from
+/// pure white noise.
+fn build_grain_template(seed: u64) -> Vec<i8> {
+    let mut state = seed;
+    let mut template = vec![0i8; GRAIN_TEMPLATE_SIZE * GRAIN_TEMPLATE_SIZE];
+    let a = 0.5;
+    let mut prev = 0.0f32;
+
+    for cell in template.iter_mut() {
+        let bits = next_lcg(&mut state);
+        let noise = ((bits >> 32) as u32 as f32 / u32::MAX as f32) * 2.0 - 1.0;
+        let g = (a * prev + noise * 127.0).round().clamp(-127.0, 127.0);
+        prev = g;
+        *cell = g as i8;
+    }
+
+    template
+}
+
+/// Linearly interpolate `(luminance, strength)` control points into a
+/// 256-entry scaling LUT.
+fn build_grain_lut(points: &[(f32, f32)]) -> [f32; 256] {
+    let mut lut = [0.0f32; 256];
+    if points.is_empty() {
+        return lut;
+    }
+
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    for (gray, slot) in lut.iter_mut().enumerate() {
+        let g = gray as f32;
+        *slot = if g <= sorted[0].0 {
+            sorted[0].1
+        } else if g >= sorted[sorted.len() - 1].0 {
+            sorted[sorted.len() - 1].1
+        } else {
+            let hi = sorted.iter().position(|p| p.0 >= g).unwrap();
+            let (lo_point, hi_point) = (sorted[hi - 1], sorted[hi]);
+            let span = (hi_point.0 - lo_point.0).max(f32::EPSILON);
+            let t = (g - lo_point.0) / span;
+            lo_point.1 + t * (hi_point.1 - lo_point.1)
+        };
+    }
+
+    lut
+}
+
+/// Persistent film-grain state: the precomputed template and scaling LUT,
+/// plus the rolling offset that animates the grain from frame to frame.
+pub struct GrainState {
+    control_points: Vec<(f32, f32)>,
+    lut: [f32; 256],
+    template: Vec<i8>,
+    frame_offset: usize,
+}
+
+impl GrainState {
+    /// Recompute the scaling LUT only if the control points actually changed.
+    fn ensure(&mut self, control_points: &[(f32, f32)]) {
+        if self.control_points != control_points {
+            self.control_points = control_points.to_vec();
+            self.lut = build_grain_lut(control_points);
+        }
+    }
+
+    /// Grain offset (in luminance units) for one output cell. Each axis
+    /// tiles the template independently (rather than through a single flat
+    /// index) so the pattern doesn't shear once `out_width` exceeds
+    /// `GRAIN_TEMPLATE_SIZE`.
+    fn sample(&self, ox: usize, oy: usize) -> f32 {
+        let idx = (oy % GRAIN_TEMPLATE_SIZE) * GRAIN_TEMPLATE_SIZE
+            + (ox + self.frame_offset) % GRAIN_TEMPLATE_SIZE;
+        self.template[idx] as f32
+    }
+
+    fn advance_frame(&mut self) {
+        self.frame_offset = (self.frame_offset + 1) % self.template.len();
+    }
+}
+
+impl Default for GrainState {
+    fn default() -> Self {
+        let control_points = vec![(0.0, 0.0), (128.0, 0.0), (255.0, 0.0)];
+        GrainState {
+            lut: build_grain_lut(&control_points),
+            control_points,
+            template: build_grain_template(0x5EED_F00D),
+            frame_offset: 0,
+        }
+    }
+}
+
+/// Add animated film grain to a grayscale value, scaled by how much grain
+/// that luminance should carry.
+#[inline]
+fn apply_grain(gray: u8, grain_state: &GrainState, ox: usize, oy: usize) -> u8 {
+    let strength = grain_state.lut[gray as usize];
+    let contribution = grain_state.sample(ox, oy) * strength / 255.0;
+    (gray as f32 + contribution).clamp(0.0, 255.0) as u8
+}
+
 /// Process a frame of RGBA pixels and produce ASCII output
 pub fn process_frame(
     config: &Config,
+    cache: &mut ResampleTables,
+    ae_state: &mut AutoExposureState,
+    grain_state: &mut GrainState,
     pixels: &[u8],
     src_width: u32,
     src_height: u32,
@@ -33,50 +548,333 @@ pub fn process_frame(
 ) {
     output.clear();
 
-    // Calculate scaling factors
-    // ASCII chars are ~2x taller than wide, so we sample more Y pixels
-    let scale_x = src_width as f32 / out_width as f32;
-    let scale_y = src_height as f32 / out_height as f32;
-
-    let bytes_per_row = src_width as usize * 4; // RGBA = 4 bytes
+    cache.ensure(
+        src_width,
+        src_height,
+        out_width,
+        out_height,
+        config.resample_filter,
+    );
+    debug_assert_eq!(cache.x_taps.len(), out_width as usize);
+    debug_assert_eq!(cache.y_taps.len(), out_height as usize);
 
-    for y in 0..out_height {
-        for x in 0..out_width {
-            // Mirror horizontally for natural webcam feel
-            let src_x = ((out_width - 1 - x) as f32 * scale_x) as usize;
-            let src_y = (y as f32 * scale_y) as usize;
+    let src_width = src_width as usize;
+    let src_height = src_height as usize;
 
-            // Bounds checking
-            let src_x = src_x.min(src_width as usize - 1);
-            let src_y = src_y.min(src_height as usize - 1);
+    let (gray_plane, histogram) = grayscale_plane(pixels, src_width, src_height);
+    let ae_frame = auto_exposure_frame(config, ae_state, &histogram, src_width * src_height);
+    let mut resampled = resample_plane(cache, &gray_plane, src_width);
 
-            // RGBA format: R at offset 0, G at 1, B at 2, A at 3
-            let pixel_offset = src_y * bytes_per_row + src_x * 4;
+    let out_width = cache.x_taps.len();
+    let out_height = cache.y_taps.len();
+    apply_crt(
+        &mut resampled,
+        out_width,
+        out_height,
+        config.vignette,
+        config.scanlines,
+    );
 
-            if pixel_offset + 2 >= pixels.len() {
-                output.push(' ');
-                continue;
-            }
-
-            let r = pixels[pixel_offset];
-            let g = pixels[pixel_offset + 1];
-            let b = pixels[pixel_offset + 2];
+    if config.grain {
+        grain_state.ensure(&config.grain_points);
+    }
 
-            // Convert to grayscale
-            let mut gray = rgba_to_gray(r, g, b);
+    for oy in 0..out_height {
+        for ox in 0..out_width {
+            let mut gray = resampled[oy * out_width + ox].clamp(0.0, 255.0) as u8;
 
-            // Apply brightness/contrast
-            gray = apply_brightness_contrast(gray, config.brightness, config.contrast);
+            // Apply auto-exposure (if enabled) composed with manual brightness/contrast
+            gray = apply_brightness_contrast(
+                gray,
+                config.brightness,
+                config.contrast,
+                ae_frame.as_ref(),
+            );
 
             // Apply inversion if enabled
             if config.invert {
                 gray = 255 - gray;
             }
 
+            // Overlay film grain, if enabled, just before glyph mapping
+            if config.grain {
+                gray = apply_grain(gray, grain_state, ox, oy);
+            }
+
             // Map to ASCII character
             let ascii_char = gray_to_ascii(gray, config.use_detailed_ramp);
             output.push(ascii_char);
         }
         output.push('\n');
     }
+
+    if config.grain {
+        grain_state.advance_frame();
+    }
+}
+
+/// Split an RGBA source frame into separate R/G/B planes (as `f32`, for
+/// reuse with [`resample_plane`]).
+/// `shift` offsets the R channel `-shift` pixels and the B channel
+/// `+shift` pixels horizontally (clamped to the frame bounds) to fake
+/// chromatic-aberration bleed; G is left unshifted.
+fn rgb_planes(
+    pixels: &[u8],
+    src_width: usize,
+    src_height: usize,
+    shift: i32,
+) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    let bytes_per_row = src_width * 4;
+    let mut r_plane = vec![0.0f32; src_width * src_height];
+    let mut g_plane = vec![0.0f32; src_width * src_height];
+    let mut b_plane = vec![0.0f32; src_width * src_height];
+
+    let sample = |x: usize, y: usize, channel: usize, dx: i32| -> u8 {
+        let shifted_x = (x as i32 + dx).clamp(0, src_width as i32 - 1) as usize;
+        let offset = y * bytes_per_row + shifted_x * 4 + channel;
+        if offset < pixels.len() {
+            pixels[offset]
+        } else {
+            0
+        }
+    };
+
+    for y in 0..src_height {
+        for x in 0..src_width {
+            let i = y * src_width + x;
+            r_plane[i] = sample(x, y, 0, -shift) as f32;
+            g_plane[i] = sample(x, y, 1, 0) as f32;
+            b_plane[i] = sample(x, y, 2, shift) as f32;
+        }
+    }
+    (r_plane, g_plane, b_plane)
+}
+
+/// Process a frame of RGBA pixels into ASCII output carrying 24-bit SGR
+/// color escapes, one glyph (from the luminance ramp) colored by the
+/// resampled R/G/B average of its source region.
+pub fn process_frame_ansi(
+    config: &Config,
+    cache: &mut ResampleTables,
+    ae_state: &mut AutoExposureState,
+    grain_state: &mut GrainState,
+    pixels: &[u8],
+    src_width: u32,
+    src_height: u32,
+    out_width: u32,
+    out_height: u32,
+    output: &mut String,
+) {
+    output.clear();
+
+    cache.ensure(
+        src_width,
+        src_height,
+        out_width,
+        out_height,
+        config.resample_filter,
+    );
+
+    let src_width = src_width as usize;
+    let src_height = src_height as usize;
+
+    let (gray_plane, histogram) = grayscale_plane(pixels, src_width, src_height);
+    let ae_frame = auto_exposure_frame(config, ae_state, &histogram, src_width * src_height);
+    let mut resampled_gray = resample_plane(cache, &gray_plane, src_width);
+
+    let shift = config.rgb_shift.round() as i32;
+    let (r_plane, g_plane, b_plane) = rgb_planes(pixels, src_width, src_height, shift);
+    let mut resampled_r = resample_plane(cache, &r_plane, src_width);
+    let mut resampled_g = resample_plane(cache, &g_plane, src_width);
+    let mut resampled_b = resample_plane(cache, &b_plane, src_width);
+
+    let out_width = cache.x_taps.len();
+    let out_height = cache.y_taps.len();
+    apply_crt(
+        &mut resampled_gray,
+        out_width,
+        out_height,
+        config.vignette,
+        config.scanlines,
+    );
+    apply_crt(
+        &mut resampled_r,
+        out_width,
+        out_height,
+        config.vignette,
+        config.scanlines,
+    );
+    apply_crt(
+        &mut resampled_g,
+        out_width,
+        out_height,
+        config.vignette,
+        config.scanlines,
+    );
+    apply_crt(
+        &mut resampled_b,
+        out_width,
+        out_height,
+        config.vignette,
+        config.scanlines,
+    );
+
+    if config.grain {
+        grain_state.ensure(&config.grain_points);
+    }
+
+    for oy in 0..out_height {
+        for ox in 0..out_width {
+            let idx = oy * out_width + ox;
+            let mut gray = resampled_gray[idx].clamp(0.0, 255.0) as u8;
+            gray = apply_brightness_contrast(
+                gray,
+                config.brightness,
+                config.contrast,
+                ae_frame.as_ref(),
+            );
+            if config.invert {
+                gray = 255 - gray;
+            }
+
+            // Overlay film grain, if enabled, just before glyph mapping
+            if config.grain {
+                gray = apply_grain(gray, grain_state, ox, oy);
+            }
+
+            let r = resampled_r[idx].clamp(0.0, 255.0) as u8;
+            let g = resampled_g[idx].clamp(0.0, 255.0) as u8;
+            let b = resampled_b[idx].clamp(0.0, 255.0) as u8;
+
+            output.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+            output.push(gray_to_ascii(gray, config.use_detailed_ramp));
+        }
+        output.push_str("\x1b[0m\n");
+    }
+
+    if config.grain {
+        grain_state.advance_frame();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_filter_is_single_nearest_tap_on_downscale() {
+        // 12 -> 4 is a 3x downscale; Point must still take exactly one
+        // source tap per output index, not a multi-tap box average.
+        let taps = build_axis_taps(ResampleFilter::Point, 12, 4, false);
+        assert_eq!(taps.len(), 4);
+        for tap in &taps {
+            assert_eq!(tap.weights, vec![1.0]);
+        }
+    }
+
+    #[test]
+    fn point_filter_picks_expected_nearest_index() {
+        let taps = build_axis_taps(ResampleFilter::Point, 12, 4, false);
+        // scale = 3.0, center(o) = (o + 0.5) * 3.0 - 0.5
+        let expected_starts = [1usize, 4, 7, 10];
+        for (tap, &expected) in taps.iter().zip(expected_starts.iter()) {
+            assert_eq!(tap.start, expected);
+        }
+    }
+
+    #[test]
+    fn histogram_stats_percentile_boundaries() {
+        // 100 samples: one each at 0..100, so the 1st percentile sits right
+        // at gray=1 and the 99th right at gray=98 (cumulative>=99 first
+        // reached there; see histogram_stats's `>=`/`>` split).
+        let mut histogram = [0u32; 256];
+        for gray in 0..100u32 {
+            histogram[gray as usize] = 1;
+        }
+        let (mean, lo, hi) = histogram_stats(&histogram, 100);
+        assert!((mean - 49.5).abs() < 1e-3);
+        assert_eq!(lo, 1.0);
+        assert_eq!(hi, 98.0);
+    }
+
+    #[test]
+    fn histogram_stats_empty_histogram() {
+        let histogram = [0u32; 256];
+        let (mean, lo, hi) = histogram_stats(&histogram, 0);
+        assert_eq!((mean, lo, hi), (0.0, 0.0, 255.0));
+    }
+
+    #[test]
+    fn grain_sample_tiles_columns_without_bleeding_into_the_next_row() {
+        // Under the old flat-index formula, (oy*64 + ox + offset) % 4096,
+        // a column past 64 on row 0 would read the next row's template
+        // data (e.g. ox=70, oy=0 landed on template index 70, i.e. row 1
+        // col 6, rather than row 0 col 6). Each axis must now wrap
+        // independently, so row 0 at ox=70 matches row 0 at ox=6.
+        let grain_state = GrainState::default();
+        assert_eq!(grain_state.sample(70, 0), grain_state.sample(6, 0));
+    }
+
+    #[test]
+    fn grain_sample_matches_per_axis_tiling_formula() {
+        let grain_state = GrainState::default();
+        for oy in 0..5usize {
+            for ox in 0..(GRAIN_TEMPLATE_SIZE * 2 + 10) {
+                let expected_idx = (oy % GRAIN_TEMPLATE_SIZE) * GRAIN_TEMPLATE_SIZE
+                    + (ox + grain_state.frame_offset) % GRAIN_TEMPLATE_SIZE;
+                assert_eq!(
+                    grain_state.sample(ox, oy),
+                    grain_state.template[expected_idx] as f32,
+                    "oy={oy} ox={ox}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn process_frame_ansi_changes_when_grain_toggled() {
+        let mut config = Config::default();
+        config.grain_points = vec![(0.0, 60.0), (128.0, 60.0), (255.0, 60.0)];
+        let mut cache = ResampleTables::default();
+        let mut ae_state = AutoExposureState::default();
+        let mut grain_state = GrainState::default();
+        let (w, h) = (100u32, 40u32);
+        let pixels = vec![128u8; (w * h * 4) as usize];
+        let mut output = String::new();
+
+        config.grain = false;
+        process_frame_ansi(
+            &config,
+            &mut cache,
+            &mut ae_state,
+            &mut grain_state,
+            &pixels,
+            w,
+            h,
+            w,
+            h,
+            &mut output,
+        );
+        let without_grain = output.clone();
+
+        config.grain = true;
+        process_frame_ansi(
+            &config,
+            &mut cache,
+            &mut ae_state,
+            &mut grain_state,
+            &pixels,
+            w,
+            h,
+            w,
+            h,
+            &mut output,
+        );
+        let with_grain = output.clone();
+
+        assert_ne!(
+            without_grain, with_grain,
+            "enabling grain must change process_frame_ansi output"
+        );
+    }
 }
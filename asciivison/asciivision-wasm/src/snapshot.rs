@@ -0,0 +1,326 @@
+/// Fixed glyph cell size (in pixels) for the bitmap font used by `capture_png`.
+pub const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// Look up the 5x7 bitmap for one ASCII character. Each row is packed into
+/// the low 5 bits, bit 4 = leftmost pixel. Covers every character used by
+/// `ASCII_RAMP_DETAILED` and `ASCII_RAMP_SIMPLE`; anything else renders as
+/// a blank cell.
+fn glyph_bitmap(ch: char) -> [u8; GLYPH_HEIGHT] {
+    match ch {
+        ' ' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '!' => [
+            0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100, 0b00000,
+        ],
+        '"' => [
+            0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '#' => [
+            0b01010, 0b11111, 0b01010, 0b01010, 0b11111, 0b01010, 0b00000,
+        ],
+        '$' => [
+            0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100,
+        ],
+        '%' => [
+            0b11001, 0b11010, 0b00100, 0b01000, 0b10110, 0b10011, 0b00000,
+        ],
+        '&' => [
+            0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101,
+        ],
+        '\'' => [
+            0b00100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '(' => [
+            0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010,
+        ],
+        ')' => [
+            0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000,
+        ],
+        '*' => [
+            0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000,
+        ],
+        '+' => [
+            0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000,
+        ],
+        ',' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00100, 0b00100, 0b01000,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        '.' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        '/' => [
+            0b00001, 0b00010, 0b00100, 0b00100, 0b01000, 0b10000, 0b00000,
+        ],
+        '0' => [
+            0b01110, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110, 0b00000,
+        ],
+        '1' => [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000,
+        ],
+        '8' => [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        ':' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+        ';' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000,
+        ],
+        '<' => [
+            0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010,
+        ],
+        '=' => [
+            0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000,
+        ],
+        '>' => [
+            0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000,
+        ],
+        '?' => [
+            0b01110, 0b10001, 0b00010, 0b00100, 0b00100, 0b00000, 0b00100,
+        ],
+        '@' => [
+            0b01110, 0b10001, 0b10111, 0b10101, 0b10111, 0b10000, 0b01110,
+        ],
+        'B' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+        'C' => [
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+        'I' => [
+            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'J' => [
+            0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+        'L' => [
+            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+        ],
+        'M' => [
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+        'O' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'Q' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+        ],
+        'U' => [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'W' => [
+            0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b11011, 0b10001,
+        ],
+        'X' => [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+        'Y' => [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        'Z' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+        ],
+        '[' => [
+            0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110,
+        ],
+        '\\' => [
+            0b10000, 0b01000, 0b00100, 0b00100, 0b00010, 0b00001, 0b00000,
+        ],
+        ']' => [
+            0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110,
+        ],
+        '^' => [
+            0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '_' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111,
+        ],
+        '`' => [
+            0b01000, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        'a' => [
+            0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111,
+        ],
+        'b' => [
+            0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110,
+        ],
+        'c' => [
+            0b00000, 0b00000, 0b01111, 0b10000, 0b10000, 0b10000, 0b01111,
+        ],
+        'd' => [
+            0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111,
+        ],
+        'f' => [
+            0b00110, 0b01001, 0b01000, 0b11110, 0b01000, 0b01000, 0b01000,
+        ],
+        'h' => [
+            0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001,
+        ],
+        'i' => [
+            0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'j' => [
+            0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+        'k' => [
+            0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010,
+        ],
+        'l' => [
+            0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'm' => [
+            0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10101, 0b10101,
+        ],
+        'n' => [
+            0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001,
+        ],
+        'o' => [
+            0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'p' => [
+            0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000,
+        ],
+        'q' => [
+            0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001,
+        ],
+        'r' => [
+            0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000,
+        ],
+        't' => [
+            0b01000, 0b01000, 0b11110, 0b01000, 0b01000, 0b01001, 0b00110,
+        ],
+        'u' => [
+            0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101,
+        ],
+        'v' => [
+            0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+        ],
+        'w' => [
+            0b00000, 0b00000, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+        ],
+        'x' => [
+            0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001,
+        ],
+        'z' => [
+            0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '{' => [
+            0b00011, 0b00100, 0b00100, 0b01000, 0b00100, 0b00100, 0b00011,
+        ],
+        '|' => [
+            0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        '}' => [
+            0b11000, 0b00100, 0b00100, 0b00010, 0b00100, 0b00100, 0b11000,
+        ],
+        '~' => [
+            0b00000, 0b00000, 0b01001, 0b10101, 0b10010, 0b00000, 0b00000,
+        ],
+        _ => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+    }
+}
+
+/// Parsed cell: the glyph to draw plus its foreground color (white unless
+/// an SGR truecolor escape set it).
+struct Cell {
+    ch: char,
+    color: (u8, u8, u8),
+}
+
+/// Parse an ASCII-art grid, newline-separated, optionally carrying
+/// `\x1b[38;2;r;g;bm` / `\x1b[0m` SGR escapes (as produced by
+/// `process_frame_ansi`), into rows of cells plus the length of the widest
+/// row (`cols`); shorter rows are not padded.
+fn parse_grid(text: &str) -> (Vec<Vec<Cell>>, usize) {
+    let mut rows = Vec::new();
+    let mut cols = 0;
+
+    for line in text.lines() {
+        let mut row = Vec::new();
+        let mut color = (255u8, 255u8, 255u8);
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '\u{1b}' && chars.peek() == Some(&'[') {
+                chars.next(); // consume '['
+                let mut params = String::new();
+                for p in chars.by_ref() {
+                    if p == 'm' {
+                        break;
+                    }
+                    params.push(p);
+                }
+                let parts: Vec<&str> = params.split(';').collect();
+                if parts.as_slice() == ["0"] {
+                    color = (255, 255, 255);
+                } else if parts.len() == 5 && parts[0] == "38" && parts[1] == "2" {
+                    let r = parts[2].parse().unwrap_or(255);
+                    let g = parts[3].parse().unwrap_or(255);
+                    let b = parts[4].parse().unwrap_or(255);
+                    color = (r, g, b);
+                }
+                continue;
+            }
+            row.push(Cell { ch: c, color });
+        }
+
+        cols = cols.max(row.len());
+        rows.push(row);
+    }
+
+    (rows, cols)
+}
+
+/// Rasterize an ASCII-art grid (as produced by `process_frame` or
+/// `process_frame_ansi`) into encoded PNG bytes, using the same 5x7 glyph
+/// atlas for every character, white-on-black unless the text carries SGR
+/// truecolor escapes.
+pub fn capture_png(text: &str) -> Result<Vec<u8>, png::EncodingError> {
+    let (rows, cols) = parse_grid(text);
+
+    let img_width = (cols * GLYPH_WIDTH) as u32;
+    let img_height = (rows.len() * GLYPH_HEIGHT) as u32;
+    let mut rgba = vec![0u8; img_width as usize * img_height as usize * 4];
+    // Opaque black background.
+    for px in rgba.chunks_exact_mut(4) {
+        px[3] = 255;
+    }
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        for (col_idx, cell) in row.iter().enumerate() {
+            let bitmap = glyph_bitmap(cell.ch);
+            for (gy, bits) in bitmap.iter().enumerate() {
+                for gx in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - gx)) == 0 {
+                        continue;
+                    }
+                    let px = col_idx * GLYPH_WIDTH + gx;
+                    let py = row_idx * GLYPH_HEIGHT + gy;
+                    let offset = (py * img_width as usize + px) * 4;
+                    rgba[offset] = cell.color.0;
+                    rgba[offset + 1] = cell.color.1;
+                    rgba[offset + 2] = cell.color.2;
+                    rgba[offset + 3] = 255;
+                }
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, img_width, img_height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&rgba)?;
+    }
+
+    Ok(bytes)
+}